@@ -0,0 +1,179 @@
+use core::ptr::write_volatile;
+use crate::mmio;
+
+/// A 16-bit signed 8.8 fixed-point number, as used for the affine matrix coefficients
+/// PA/PB/PC/PD and the rotation/scale helpers in this module.
+pub type Fixed8_8 = i16;
+
+/// One quarter-turn's worth of entries in [`SIN_LUT`]; `SIN_LUT_LEN / 4`.
+const SIN_LUT_QUARTER: usize = 64;
+/// Number of entries in [`SIN_LUT`], i.e. the resolution of a full turn.
+const SIN_LUT_LEN: usize = 256;
+
+/// `sin(2*pi*i/256)` for `i` in `0..256`, scaled to 8.8 fixed-point (so 256 = 1.0).
+///
+/// Used by [`affine_matrix_from_rotation_scale`]; `angle` there is in the same 0-255
+/// units, i.e. a full turn is 256 rather than 360 or 2*pi.
+static SIN_LUT: [Fixed8_8; SIN_LUT_LEN] = [
+    0, 6, 13, 19, 25, 31, 38, 44, 50, 56, 62, 68, 74, 80, 86, 92,
+    98, 104, 109, 115, 121, 126, 132, 137, 142, 147, 152, 157, 162, 167, 172, 177,
+    181, 185, 190, 194, 198, 202, 206, 209, 213, 216, 220, 223, 226, 229, 231, 234,
+    237, 239, 241, 243, 245, 247, 248, 250, 251, 252, 253, 254, 255, 255, 256, 256,
+    256, 256, 256, 255, 255, 254, 253, 252, 251, 250, 248, 247, 245, 243, 241, 239,
+    237, 234, 231, 229, 226, 223, 220, 216, 213, 209, 206, 202, 198, 194, 190, 185,
+    181, 177, 172, 167, 162, 157, 152, 147, 142, 137, 132, 126, 121, 115, 109, 104,
+    98, 92, 86, 80, 74, 68, 62, 56, 50, 44, 38, 31, 25, 19, 13, 6,
+    0, -6, -13, -19, -25, -31, -38, -44, -50, -56, -62, -68, -74, -80, -86, -92,
+    -98, -104, -109, -115, -121, -126, -132, -137, -142, -147, -152, -157, -162, -167, -172, -177,
+    -181, -185, -190, -194, -198, -202, -206, -209, -213, -216, -220, -223, -226, -229, -231, -234,
+    -237, -239, -241, -243, -245, -247, -248, -250, -251, -252, -253, -254, -255, -255, -256, -256,
+    -256, -256, -256, -255, -255, -254, -253, -252, -251, -250, -248, -247, -245, -243, -241, -239,
+    -237, -234, -231, -229, -226, -223, -220, -216, -213, -209, -206, -202, -198, -194, -190, -185,
+    -181, -177, -172, -167, -162, -157, -152, -147, -142, -137, -132, -126, -121, -115, -109, -104,
+    -98, -92, -86, -80, -74, -68, -62, -56, -50, -44, -38, -31, -25, -19, -13, -6,
+];
+
+/// Looks up `sin(2*pi*angle/256)` in 8.8 fixed-point; `angle` wraps every 256 units.
+fn sin_lut(angle: u8) -> i32 {
+    SIN_LUT[angle as usize] as i32
+}
+
+/// Looks up `cos(2*pi*angle/256)` in 8.8 fixed-point; `angle` wraps every 256 units.
+fn cos_lut(angle: u8) -> i32 {
+    SIN_LUT[angle.wrapping_add(SIN_LUT_QUARTER as u8) as usize] as i32
+}
+
+/// The PA/PB/PC/PD coefficients of a BG2/BG3 affine transform, in 8.8 fixed-point.
+///
+/// Applied by the hardware to map each destination screen pixel back to a source
+/// texel offset from the layer's reference point ([`set_main_bg2_ref_point`] etc.):
+/// `(dx, dy) = ((PA*x + PB*y) >> 8, (PC*x + PD*y) >> 8)`, where `(x, y)` is the pixel's
+/// offset from the top-left of the screen.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BgAffineMatrix {
+    pub pa: Fixed8_8,
+    pub pb: Fixed8_8,
+    pub pc: Fixed8_8,
+    pub pd: Fixed8_8,
+}
+
+impl BgAffineMatrix {
+    /// The identity matrix: no rotation, no scaling.
+    pub const IDENTITY: Self = Self { pa: 1 << 8, pb: 0, pc: 0, pd: 1 << 8 };
+}
+
+/// Builds the [`BgAffineMatrix`] for a rotation by `angle` (0-255 representing a full
+/// turn) combined with independent horizontal/vertical zoom factors `scale_x`/`scale_y`
+/// (8.8 fixed-point; `1 << 8` is 1x, `2 << 8` is 2x zoom, and so on).
+///
+/// Since the hardware matrix maps *destination* pixels back to *source* texels, zooming
+/// the displayed image in requires dividing by the scale factor internally; this is
+/// handled for the caller so `scale_x`/`scale_y` here read as the effective on-screen zoom.
+///
+/// A `scale_x`/`scale_y` of 0 would require dividing by zero; it's treated as 1 (the
+/// smallest representable zoom step) instead of panicking.
+#[must_use]
+pub fn affine_matrix_from_rotation_scale(angle: u8, scale_x: Fixed8_8, scale_y: Fixed8_8) -> BgAffineMatrix {
+    let sin = sin_lut(angle);
+    let cos = cos_lut(angle);
+    let inv_sx = (1i32 << 16) / (if scale_x == 0 { 1 } else { scale_x as i32 });
+    let inv_sy = (1i32 << 16) / (if scale_y == 0 { 1 } else { scale_y as i32 });
+    BgAffineMatrix {
+        pa: ((cos * inv_sx) >> 8) as Fixed8_8,
+        pb: ((-sin * inv_sx) >> 8) as Fixed8_8,
+        pc: ((sin * inv_sy) >> 8) as Fixed8_8,
+        pd: ((cos * inv_sy) >> 8) as Fixed8_8,
+    }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_bg2_affine(m: BgAffineMatrix) {
+    unsafe {
+        write_volatile(mmio::BG2PA_MAIN as *mut u16, m.pa as u16);
+        write_volatile(mmio::BG2PB_MAIN as *mut u16, m.pb as u16);
+        write_volatile(mmio::BG2PC_MAIN as *mut u16, m.pc as u16);
+        write_volatile(mmio::BG2PD_MAIN as *mut u16, m.pd as u16);
+    }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_bg3_affine(m: BgAffineMatrix) {
+    unsafe {
+        write_volatile(mmio::BG3PA_MAIN as *mut u16, m.pa as u16);
+        write_volatile(mmio::BG3PB_MAIN as *mut u16, m.pb as u16);
+        write_volatile(mmio::BG3PC_MAIN as *mut u16, m.pc as u16);
+        write_volatile(mmio::BG3PD_MAIN as *mut u16, m.pd as u16);
+    }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_bg2_affine(m: BgAffineMatrix) {
+    unsafe {
+        write_volatile(mmio::BG2PA_SUB as *mut u16, m.pa as u16);
+        write_volatile(mmio::BG2PB_SUB as *mut u16, m.pb as u16);
+        write_volatile(mmio::BG2PC_SUB as *mut u16, m.pc as u16);
+        write_volatile(mmio::BG2PD_SUB as *mut u16, m.pd as u16);
+    }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_bg3_affine(m: BgAffineMatrix) {
+    unsafe {
+        write_volatile(mmio::BG3PA_SUB as *mut u16, m.pa as u16);
+        write_volatile(mmio::BG3PB_SUB as *mut u16, m.pb as u16);
+        write_volatile(mmio::BG3PC_SUB as *mut u16, m.pc as u16);
+        write_volatile(mmio::BG3PD_SUB as *mut u16, m.pd as u16);
+    }
+}
+
+/// Masks a reference-point coordinate down to the hardware's 28-bit signed (1/19/8) range.
+#[cfg(feature = "arm9")]
+fn mask_ref_point(coord: i32) -> u32 {
+    (coord as u32) & 0x0FFF_FFFF
+}
+
+/// Sets BG2's reference point (the texel under the top-left of the screen) on the main
+/// engine. `x`/`y` are signed 19.8 fixed-point values (1 sign bit, 19 integer bits, 8
+/// fraction bits); only the low 28 bits are significant to the hardware.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_bg2_ref_point(x: i32, y: i32) {
+    unsafe {
+        write_volatile(mmio::BG2X_MAIN as *mut u32, mask_ref_point(x));
+        write_volatile(mmio::BG2Y_MAIN as *mut u32, mask_ref_point(y));
+    }
+}
+
+/// Sets BG3's reference point on the main engine; see [`set_main_bg2_ref_point`].
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_bg3_ref_point(x: i32, y: i32) {
+    unsafe {
+        write_volatile(mmio::BG3X_MAIN as *mut u32, mask_ref_point(x));
+        write_volatile(mmio::BG3Y_MAIN as *mut u32, mask_ref_point(y));
+    }
+}
+
+/// Sets BG2's reference point on the sub engine; see [`set_main_bg2_ref_point`].
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_bg2_ref_point(x: i32, y: i32) {
+    unsafe {
+        write_volatile(mmio::BG2X_SUB as *mut u32, mask_ref_point(x));
+        write_volatile(mmio::BG2Y_SUB as *mut u32, mask_ref_point(y));
+    }
+}
+
+/// Sets BG3's reference point on the sub engine; see [`set_main_bg2_ref_point`].
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_bg3_ref_point(x: i32, y: i32) {
+    unsafe {
+        write_volatile(mmio::BG3X_SUB as *mut u32, mask_ref_point(x));
+        write_volatile(mmio::BG3Y_SUB as *mut u32, mask_ref_point(y));
+    }
+}
@@ -0,0 +1,182 @@
+//! The four DMA channels, including the display-synchronised HBlank/VBlank start timings.
+//!
+//! Unlike a CPU copy, a DMA transfer set to start on HBlank or VBlank fires automatically
+//! each time the display reaches that point in the frame, which is how effects like
+//! per-scanline palette/scroll changes ("HDMA") are done without CPU intervention every line.
+
+use core::ptr::write_volatile;
+use modular_bitfield::prelude::*;
+use crate::mmio;
+
+/// Address-control mode for a DMA channel's source or destination pointer.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 2]
+pub enum DmaAddrControl {
+    Increment = 0,
+    Decrement = 1,
+    Fixed = 2,
+    /// Destination only: increments during the transfer, then resets to its original
+    /// value afterwards, ready for the next repeat.
+    IncrementReload = 3,
+}
+
+/// When a DMA transfer (re)starts. This is the ARM9 (DS-specific) 3-bit start-timing
+/// field, which is wider than the GBA's 2-bit equivalent.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 3]
+pub enum DmaStartTiming {
+    /// Starts as soon as the channel is enabled.
+    Immediate = 0,
+    /// Starts at the beginning of VBlank, once per frame.
+    VBlank = 1,
+    /// Starts at the beginning of every visible scanline's HBlank (lines 0-191); does
+    /// nothing during VBlank. See [`start_hblank`] for the caveats this implies.
+    HBlank = 2,
+    /// Synced to the display start.
+    DisplayStart = 3,
+    /// DMA0-1 only: synced to the main memory display FIFO.
+    MainMemoryDisplay = 4,
+    /// DMA2-3 only: synced to a DS cart (Slot-1) data word becoming available.
+    DsCartSlot = 5,
+    /// DMA2-3 only: synced to a GBA cart (Slot-2) data word becoming available.
+    GbaCartSlot = 6,
+    /// DMA0 only: synced to the 3D geometry command FIFO.
+    GeometryCommandFifo = 7,
+}
+
+/// Controls how a DMA channel moves data: address stepping, unit size, repeat, and
+/// start timing.
+#[bitfield]
+#[repr(u16)]
+pub struct DmaControl {
+    /// Bits 16-20 of the 21-bit transfer length. Not meant to be set directly: `set_control`
+    /// derives this from its `count` argument and overwrites whatever is set here.
+    word_count_high: B5,
+    #[bits = 2]
+    pub dest_addr_control: DmaAddrControl,
+    #[bits = 2]
+    pub src_addr_control: DmaAddrControl,
+    /// Restart the transfer at the next `start_timing` trigger instead of running once.
+    pub repeat: bool,
+    /// Transfer 32-bit words instead of 16-bit halfwords.
+    pub transfer_32bit: bool,
+    #[bits = 3]
+    pub start_timing: DmaStartTiming,
+    pub irq_enable: bool,
+    pub enabled: bool,
+}
+
+/// Byte offset between successive DMA channels' register blocks.
+#[cfg(feature = "arm9")]
+const CHANNEL_STRIDE: usize = 0xC;
+
+#[cfg(feature = "arm9")]
+fn channel_sad(channel: usize) -> usize {
+    mmio::DMA0SAD + channel * CHANNEL_STRIDE
+}
+
+#[cfg(feature = "arm9")]
+fn channel_dad(channel: usize) -> usize {
+    mmio::DMA0DAD + channel * CHANNEL_STRIDE
+}
+
+#[cfg(feature = "arm9")]
+fn channel_cnt_l(channel: usize) -> usize {
+    mmio::DMA0CNT_L + channel * CHANNEL_STRIDE
+}
+
+#[cfg(feature = "arm9")]
+fn channel_cnt_h(channel: usize) -> usize {
+    mmio::DMA0CNT_H + channel * CHANNEL_STRIDE
+}
+
+/// Sets DMA channel `channel`'s (0-3) source address. Must not be changed while the
+/// channel is enabled.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_source(channel: usize, src: *const u8) {
+    unsafe { write_volatile(channel_sad(channel & 0x3) as *mut u32, src as u32); }
+}
+
+/// Sets DMA channel `channel`'s (0-3) destination address. Must not be changed while the
+/// channel is enabled.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_dest(channel: usize, dst: *mut u8) {
+    unsafe { write_volatile(channel_dad(channel & 0x3) as *mut u32, dst as u32); }
+}
+
+/// Maximum transfer length: 21 bits' worth of units (16-bit or 32-bit, depending on
+/// [`DmaControl::transfer_32bit`]).
+pub const MAX_WORD_COUNT: u32 = (1 << 21) - 1;
+
+/// Sets DMA channel `channel`'s (0-3) transfer unit count (16-bit or 32-bit units,
+/// depending on [`DmaControl::transfer_32bit`], up to [`MAX_WORD_COUNT`]) and control
+/// word, starting the transfer if [`DmaControl::enabled`] is set.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_control(channel: usize, count: u32, control: DmaControl) {
+    let channel = channel & 0x3;
+    let count = count & MAX_WORD_COUNT;
+    let control = control.with_word_count_high((count >> 16) as u8);
+    unsafe {
+        write_volatile(channel_cnt_l(channel) as *mut u16, count as u16);
+        write_volatile(channel_cnt_h(channel) as *mut u16, u16::from(control));
+    }
+}
+
+/// Starts an immediate one-shot transfer of `count` units from `src` to `dst` on DMA
+/// channel `channel` (0-3), incrementing both pointers.
+#[cfg(feature = "arm9")]
+pub fn start_immediate(channel: usize, src: *const u8, dst: *mut u8, count: u32, transfer_32bit: bool) {
+    set_source(channel, src);
+    set_dest(channel, dst);
+    set_control(channel, count, DmaControl::new()
+        .with_dest_addr_control(DmaAddrControl::Increment)
+        .with_src_addr_control(DmaAddrControl::Increment)
+        .with_transfer_32bit(transfer_32bit)
+        .with_start_timing(DmaStartTiming::Immediate)
+        .with_enabled(true));
+}
+
+/// Arms DMA channel `channel` (0-3) to transfer `count` units from `src` to `dst` once,
+/// at the next VBlank.
+#[cfg(feature = "arm9")]
+pub fn start_vblank(channel: usize, src: *const u8, dst: *mut u8, count: u32, transfer_32bit: bool) {
+    set_source(channel, src);
+    set_dest(channel, dst);
+    set_control(channel, count, DmaControl::new()
+        .with_dest_addr_control(DmaAddrControl::Increment)
+        .with_src_addr_control(DmaAddrControl::Increment)
+        .with_transfer_32bit(transfer_32bit)
+        .with_start_timing(DmaStartTiming::VBlank)
+        .with_enabled(true));
+}
+
+/// Arms DMA channel `channel` (0-3) to transfer `count` units from `src` to `dst` during
+/// every HBlank of every visible scanline (lines 0-191), repeating automatically; this is
+/// the basis for per-scanline ("HDMA") effects like gradient backgrounds or wobble.
+///
+/// The source pointer typically uses [`DmaAddrControl::Increment`] so each HBlank pulls
+/// the next line's worth of data (e.g. the next entry of a per-line scroll/palette table),
+/// while the destination stays [`DmaAddrControl::Fixed`] on the MMIO register being
+/// updated each line. The transfer does nothing during VBlank (lines 192-262) and the
+/// caller must not touch the channel's registers again until it's done repeating.
+#[cfg(feature = "arm9")]
+pub fn start_hblank(channel: usize, src: *const u8, dst: *mut u8, count: u32, transfer_32bit: bool) {
+    set_source(channel, src);
+    set_dest(channel, dst);
+    set_control(channel, count, DmaControl::new()
+        .with_dest_addr_control(DmaAddrControl::Fixed)
+        .with_src_addr_control(DmaAddrControl::Increment)
+        .with_repeat(true)
+        .with_transfer_32bit(transfer_32bit)
+        .with_start_timing(DmaStartTiming::HBlank)
+        .with_enabled(true));
+}
+
+/// Disables DMA channel `channel` (0-3), stopping any repeating transfer.
+#[cfg(feature = "arm9")]
+pub fn stop(channel: usize) {
+    set_control(channel, 0, DmaControl::new());
+}
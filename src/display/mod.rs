@@ -1,6 +1,9 @@
 pub mod console;
 mod vram;
 pub use vram::*;
+mod affine;
+pub use affine::*;
+pub mod dma;
 
 use core::ptr::{read_volatile, write_volatile};
 use bitflags::bitflags;
@@ -11,6 +14,13 @@ use crate::mmio;
 #[cfg(feature = "arm9")]
 const POWCNT1: VolAddress<u32, Safe, Safe> = unsafe { VolAddress::new(mmio::POWCNT1) };
 
+/// Width of the screen in pixels, used to clip window rectangles to the screen edge.
+#[cfg(feature = "arm9")]
+const SCREEN_WIDTH: u8 = 240;
+/// Height of the screen in pixels, used to clip window rectangles to the screen edge.
+#[cfg(feature = "arm9")]
+const SCREEN_HEIGHT: u8 = 192;
+
 // Used with power_on and power_off
 bitflags! {
     #[repr(transparent)]
@@ -25,12 +35,83 @@ bitflags! {
     }
 }
 
+/// The BG mode, selecting how many backgrounds are available and whether they're
+/// text (tiled), affine (rotation/scaling), large bitmap, or 3D. Main engine only;
+/// see [`BgModeSub`] for the sub engine's more limited set.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 3]
+pub enum BgMode {
+    /// BG0-3 all text.
+    Mode0 = 0,
+    /// BG0-1 text, BG2 affine.
+    Mode1 = 1,
+    /// BG0-1 text, BG2-3 affine.
+    Mode2 = 2,
+    /// BG0-2 text, BG3 extended (bitmap-capable) affine.
+    Mode3 = 3,
+    /// BG0 text, BG1 text, BG2 affine, BG3 extended affine.
+    Mode4 = 4,
+    /// BG0 text, BG1 text, BG2-3 extended (bitmap-capable) affine.
+    Mode5 = 5,
+    /// BG0 is the 3D engine's output; BG2 is a large bitmap (512x1024 or 1024x512).
+    Mode6 = 6,
+    Reserved7 = 7,
+}
+
+/// The BG mode for the sub engine, which lacks 3D and the large-bitmap mode 6.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 3]
+pub enum BgModeSub {
+    /// BG0-3 all text.
+    Mode0 = 0,
+    /// BG0-1 text, BG2 affine.
+    Mode1 = 1,
+    /// BG0-1 text, BG2-3 affine.
+    Mode2 = 2,
+    /// BG0-2 text, BG3 extended (bitmap-capable) affine.
+    Mode3 = 3,
+    /// BG0 text, BG1 text, BG2 affine, BG3 extended affine.
+    Mode4 = 4,
+    /// BG0 text, BG1 text, BG2-3 extended (bitmap-capable) affine.
+    Mode5 = 5,
+    Reserved6 = 6,
+    Reserved7 = 7,
+}
+
+/// Whether sprite (OBJ) tile data is laid out as a 1D or 2D array in VRAM.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 1]
+pub enum ObjMapping {
+    /// Tiles are arranged as a 2D array; a sprite's tiles wrap at a fixed stride
+    /// regardless of the sprite's own width.
+    TwoDimensional = 0,
+    /// Tiles are arranged as a flat 1D array, one after another for each sprite.
+    OneDimensional = 1,
+}
+
+/// What DISPCNT's display output actually shows.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 2]
+pub enum DisplayMode {
+    /// Screen shows a fixed white colour; nothing is rendered.
+    Off = 0,
+    /// Normal rendering from BG/OBJ layers (the common case).
+    Graphics = 1,
+    /// Main engine only: displays a raw block of VRAM (`vram_display_block`) rather
+    /// than rendering.
+    VramDisplay = 2,
+    /// Main engine only: displays data streamed in through the main memory display FIFO.
+    MainMemoryFifo = 3,
+}
+
 #[bitfield]
 #[repr(u32)]
 pub struct DisplayControlMain {
-    pub bg_mode: B3, // enum
+    #[bits = 3]
+    pub bg_mode: BgMode,
     pub bg0_3d: bool,
-    pub tile_obj_mapping: bool, // enum
+    #[bits = 1]
+    pub tile_obj_mapping: ObjMapping,
     pub bm_obj_2d_dim: bool, // enum
     pub bm_obj_mapping: bool, // enum
     pub forced_blank: bool,
@@ -42,7 +123,8 @@ pub struct DisplayControlMain {
     pub display_win0: bool,
     pub display_win1: bool,
     pub display_obj_win: bool,
-    pub display_mode: B2, // enum
+    #[bits = 2]
+    pub display_mode: DisplayMode,
     pub vram_display_block: B2, // enum
     pub tile_obj_1d_bound: B2,
     pub bm_obj_1d_bound: B1,
@@ -56,9 +138,11 @@ pub struct DisplayControlMain {
 #[bitfield]
 #[repr(u32)]
 pub struct DisplayControlSub {
-    pub bg_mode: B3, // enum (different)
+    #[bits = 3]
+    pub bg_mode: BgModeSub,
     #[skip] __: bool,
-    pub tile_obj_mapping: bool, // enum
+    #[bits = 1]
+    pub tile_obj_mapping: ObjMapping,
     pub bm_obj_2d_dim: bool, // enum
     pub bm_obj_mapping: bool, // enum
     pub forced_blank: bool,
@@ -70,7 +154,10 @@ pub struct DisplayControlSub {
     pub display_win0: bool,
     pub display_win1: bool,
     pub display_obj_win: bool,
-    pub display_mode: B2, // enum (different)
+    /// Only `Off` and `Graphics` are meaningful on the sub engine; `VramDisplay` and
+    /// `MainMemoryFifo` are main-engine-only features.
+    #[bits = 2]
+    pub display_mode: DisplayMode,
     #[skip] __: B2,
     pub tile_obj_1d_bound: B2,
     #[skip] __: B1,
@@ -80,16 +167,153 @@ pub struct DisplayControlSub {
     pub obj_ext_pal_enabled: bool,
 }
 
+/// Whether a background's tiles are drawn from a 16-colour or 256-colour palette.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 1]
+pub enum PaletteMode {
+    /// 16 colours per tile, selectable per-tile from 16 palette banks.
+    Color16 = 0,
+    /// 256 colours per tile, shared across the whole background (or an extended
+    /// palette slot if `bg_ext_pal_enabled` is set).
+    Color256 = 1,
+}
+
+/// The BG screen size; the raw 2-bit value means different things for a text (tiled)
+/// background than for an affine (rotation/scaling) one.
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 2]
+pub enum ScreenSize {
+    /// Text: 256x256px (32x32 tiles). Affine: 128x128px (16x16 tiles).
+    Size0 = 0,
+    /// Text: 512x256px (64x32 tiles). Affine: 256x256px (32x32 tiles).
+    Size1 = 1,
+    /// Text: 256x512px (32x64 tiles). Affine: 512x512px (64x64 tiles).
+    Size2 = 2,
+    /// Text: 512x512px (64x64 tiles). Affine: 1024x1024px (128x128 tiles).
+    Size3 = 3,
+}
+
 #[bitfield]
 #[repr(u16)]
 pub struct BackgroundControl {
     pub priority: B2, // lower = higher priority
     pub tiledata_base: B4,
     pub mosaic_enabled: bool,
-    pub palette_setting: B1, // enum
+    #[bits = 1]
+    pub palette_setting: PaletteMode,
     pub tilemap_base: B5,
     pub bit13: B1, // BG0/BG1 = Ext Palette Slot. BG2/BG3 = Display Area Overflow (0=Transparent, 1=Wraparound)
-    pub screen_size: B2,
+    #[bits = 2]
+    pub screen_size: ScreenSize,
+}
+
+/// A colour special effect applied to the layers selected by [`BlendControl`].
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq, Eq)]
+#[bits = 2]
+pub enum BlendEffect {
+    Off = 0,
+    /// Alpha blend between the topmost 1st-target pixel and the topmost 2nd-target pixel
+    /// beneath it, per channel `min(31, top*EVA/16 + bottom*EVB/16)` (see [`BlendAlpha`]).
+    AlphaBlend = 1,
+    /// Brighten 1st-target pixels towards white, per channel `I + (31-I)*EVY/16`
+    /// (see [`BlendBrightness`]).
+    Brighten = 2,
+    /// Darken 1st-target pixels towards black, per channel `I - I*EVY/16`
+    /// (see [`BlendBrightness`]).
+    Darken = 3,
+}
+
+/// Selects the layers participating in a colour special effect and which effect applies.
+///
+/// The 1st target bits mark the layers drawn "on top" (BG0-3, OBJ, backdrop) and the 2nd
+/// target bits mark the layers drawn "underneath"; which ones matter depends on `effect`
+/// (see [`BlendEffect`]). All per-channel blend results are clamped to 0-31.
+#[bitfield]
+#[repr(u16)]
+pub struct BlendControl {
+    pub bg0_1st_target: bool,
+    pub bg1_1st_target: bool,
+    pub bg2_1st_target: bool,
+    pub bg3_1st_target: bool,
+    pub obj_1st_target: bool,
+    pub backdrop_1st_target: bool,
+    #[bits = 2]
+    pub effect: BlendEffect,
+    pub bg0_2nd_target: bool,
+    pub bg1_2nd_target: bool,
+    pub bg2_2nd_target: bool,
+    pub bg3_2nd_target: bool,
+    pub obj_2nd_target: bool,
+    pub backdrop_2nd_target: bool,
+    #[skip] __: B2,
+}
+
+/// Holds the two alpha-blend coefficients used when [`BlendControl`]'s effect is alpha blend.
+///
+/// `eva` weights the 1st target (top) layer, `evb` weights the 2nd target (bottom) layer.
+/// Both are 5-bit values from 0 to 16 (values above 16 behave the same as 16).
+#[bitfield]
+#[repr(u16)]
+pub struct BlendAlpha {
+    pub eva: B5,
+    #[skip] __: B3,
+    pub evb: B5,
+    #[skip] __: B3,
+}
+
+/// Holds the brighten/darken coefficient EVY used when [`BlendControl`]'s effect is
+/// brighten or darken.
+///
+/// 5-bit value from 0 to 16 (values above 16 behave the same as 16).
+#[bitfield]
+#[repr(u16)]
+pub struct BlendBrightness {
+    pub evy: B5,
+    #[skip] __: B11,
+}
+
+/// Per-region layer enable mask used by [`WindowControl`] for WININ/WINOUT.
+///
+/// Controls which layers are drawn, and whether colour special effects ([`BlendControl`])
+/// can apply, inside a particular window region.
+#[bitfield]
+#[derive(BitfieldSpecifier)]
+#[repr(u8)]
+pub struct WindowLayerMask {
+    pub display_bg0: bool,
+    pub display_bg1: bool,
+    pub display_bg2: bool,
+    pub display_bg3: bool,
+    pub display_obj: bool,
+    pub effects_enabled: bool,
+    #[skip] __: B2,
+}
+
+/// Selects which layers are visible, and whether colour effects apply, in each window region.
+///
+/// Written to WININ to configure win0 (`first`) and win1 (`second`), or to WINOUT to
+/// configure the area outside all windows (`first`) and the OBJ window (`second`).
+#[bitfield]
+#[repr(u16)]
+pub struct WindowControl {
+    #[bits = 8]
+    pub first: WindowLayerMask,
+    #[bits = 8]
+    pub second: WindowLayerMask,
+}
+
+/// Configures the mosaic stretch sizes used by backgrounds/objects with mosaic enabled
+/// (see `BackgroundControl::mosaic_enabled` and the object attribute equivalent).
+///
+/// Each field is a 4-bit (pixels-1) value: 0 means 1-pixel blocks (mosaic has no visible
+/// effect), 15 means 16-pixel blocks.
+#[bitfield]
+#[repr(u16)]
+pub struct Mosaic {
+    pub bg_h_size: B4,
+    pub bg_v_size: B4,
+    pub obj_h_size: B4,
+    pub obj_v_size: B4,
 }
 
 pub enum MainEnginePos {
@@ -196,6 +420,240 @@ pub fn get_sub_bg_control(bg: usize) -> BackgroundControl {
     unsafe { BackgroundControl::from(read_volatile((mmio::BG0CNT_SUB + ((bg & 0x3) * 2)) as *mut u16)) }
 }
 
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_blend_control(c: BlendControl) {
+    unsafe { write_volatile(mmio::BLDCNT_MAIN as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_main_blend_control() -> BlendControl {
+    unsafe { BlendControl::from(read_volatile(mmio::BLDCNT_MAIN as *mut u16)) }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_blend_control(c: BlendControl) {
+    unsafe { write_volatile(mmio::BLDCNT_SUB as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_sub_blend_control() -> BlendControl {
+    unsafe { BlendControl::from(read_volatile(mmio::BLDCNT_SUB as *mut u16)) }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_blend_alpha(c: BlendAlpha) {
+    unsafe { write_volatile(mmio::BLDALPHA_MAIN as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_main_blend_alpha() -> BlendAlpha {
+    unsafe { BlendAlpha::from(read_volatile(mmio::BLDALPHA_MAIN as *mut u16)) }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_blend_alpha(c: BlendAlpha) {
+    unsafe { write_volatile(mmio::BLDALPHA_SUB as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_sub_blend_alpha() -> BlendAlpha {
+    unsafe { BlendAlpha::from(read_volatile(mmio::BLDALPHA_SUB as *mut u16)) }
+}
+
+/// Sets the fade brightness (EVY) used by the main engine's brighten/darken colour effect.
+///
+/// This is distinct from [`set_brightness`], which applies a separate master brightness
+/// pass unconditionally rather than via [`BlendControl`]'s effect selection.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_blend_brightness(c: BlendBrightness) {
+    unsafe { write_volatile(mmio::BLDY_MAIN as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_main_blend_brightness() -> BlendBrightness {
+    unsafe { BlendBrightness::from(read_volatile(mmio::BLDY_MAIN as *mut u16)) }
+}
+
+/// Sets the fade brightness (EVY) used by the sub engine's brighten/darken colour effect.
+///
+/// This is distinct from [`set_brightness`], which applies a separate master brightness
+/// pass unconditionally rather than via [`BlendControl`]'s effect selection.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_blend_brightness(c: BlendBrightness) {
+    unsafe { write_volatile(mmio::BLDY_SUB as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_sub_blend_brightness() -> BlendBrightness {
+    unsafe { BlendBrightness::from(read_volatile(mmio::BLDY_SUB as *mut u16)) }
+}
+
+/// Packs a window's horizontal extent into the WINxH register format (`left << 8 | right+1`).
+///
+/// If `right` is less than `left`, or the packed right edge would run off the screen,
+/// this clips to the screen's right edge (240), matching what the hardware does.
+#[cfg(feature = "arm9")]
+fn pack_window_h(left: u8, right: u8) -> u16 {
+    let mut packed_right = right as u16 + 1;
+    if right < left || packed_right > SCREEN_WIDTH as u16 {
+        packed_right = SCREEN_WIDTH as u16;
+    }
+    ((left as u16) << 8) | packed_right
+}
+
+/// Packs a window's vertical extent into the WINxV register format (`top << 8 | bottom+1`).
+///
+/// If `bottom` is less than `top`, or the packed bottom edge would run off the screen,
+/// this clips to the screen's bottom edge (192), matching what the hardware does.
+#[cfg(feature = "arm9")]
+fn pack_window_v(top: u8, bottom: u8) -> u16 {
+    let mut packed_bottom = bottom as u16 + 1;
+    if bottom < top || packed_bottom > SCREEN_HEIGHT as u16 {
+        packed_bottom = SCREEN_HEIGHT as u16;
+    }
+    ((top as u16) << 8) | packed_bottom
+}
+
+/// Sets the horizontal extent of window 0 on the main engine, from `left` to `right` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_win0h(left: u8, right: u8) {
+    unsafe { write_volatile(mmio::WIN0H_MAIN as *mut u16, pack_window_h(left, right)); }
+}
+
+/// Sets the horizontal extent of window 1 on the main engine, from `left` to `right` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_win1h(left: u8, right: u8) {
+    unsafe { write_volatile(mmio::WIN1H_MAIN as *mut u16, pack_window_h(left, right)); }
+}
+
+/// Sets the vertical extent of window 0 on the main engine, from `top` to `bottom` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_win0v(top: u8, bottom: u8) {
+    unsafe { write_volatile(mmio::WIN0V_MAIN as *mut u16, pack_window_v(top, bottom)); }
+}
+
+/// Sets the vertical extent of window 1 on the main engine, from `top` to `bottom` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_win1v(top: u8, bottom: u8) {
+    unsafe { write_volatile(mmio::WIN1V_MAIN as *mut u16, pack_window_v(top, bottom)); }
+}
+
+/// Sets the horizontal extent of window 0 on the sub engine, from `left` to `right` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_win0h(left: u8, right: u8) {
+    unsafe { write_volatile(mmio::WIN0H_SUB as *mut u16, pack_window_h(left, right)); }
+}
+
+/// Sets the horizontal extent of window 1 on the sub engine, from `left` to `right` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_win1h(left: u8, right: u8) {
+    unsafe { write_volatile(mmio::WIN1H_SUB as *mut u16, pack_window_h(left, right)); }
+}
+
+/// Sets the vertical extent of window 0 on the sub engine, from `top` to `bottom` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_win0v(top: u8, bottom: u8) {
+    unsafe { write_volatile(mmio::WIN0V_SUB as *mut u16, pack_window_v(top, bottom)); }
+}
+
+/// Sets the vertical extent of window 1 on the sub engine, from `top` to `bottom` inclusive.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_win1v(top: u8, bottom: u8) {
+    unsafe { write_volatile(mmio::WIN1V_SUB as *mut u16, pack_window_v(top, bottom)); }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_winin(c: WindowControl) {
+    unsafe { write_volatile(mmio::WININ_MAIN as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_main_winin() -> WindowControl {
+    unsafe { WindowControl::from(read_volatile(mmio::WININ_MAIN as *mut u16)) }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_winout(c: WindowControl) {
+    unsafe { write_volatile(mmio::WINOUT_MAIN as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_main_winout() -> WindowControl {
+    unsafe { WindowControl::from(read_volatile(mmio::WINOUT_MAIN as *mut u16)) }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_winin(c: WindowControl) {
+    unsafe { write_volatile(mmio::WININ_SUB as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_sub_winin() -> WindowControl {
+    unsafe { WindowControl::from(read_volatile(mmio::WININ_SUB as *mut u16)) }
+}
+
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_winout(c: WindowControl) {
+    unsafe { write_volatile(mmio::WINOUT_SUB as *mut u16, u16::from(c)); }
+}
+
+#[must_use]
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn get_sub_winout() -> WindowControl {
+    unsafe { WindowControl::from(read_volatile(mmio::WINOUT_SUB as *mut u16)) }
+}
+
+/// Sets the mosaic stretch sizes for the main engine's backgrounds and objects.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_main_mosaic(c: Mosaic) {
+    unsafe { write_volatile(mmio::MOSAIC_MAIN as *mut u16, u16::from(c)); }
+}
+
+/// Sets the mosaic stretch sizes for the sub engine's backgrounds and objects.
+#[cfg(feature = "arm9")]
+#[inline(always)]
+pub fn set_sub_mosaic(c: Mosaic) {
+    unsafe { write_volatile(mmio::MOSAIC_SUB as *mut u16, u16::from(c)); }
+}
+
 /// Set the screen line that the VCounter is triggered for.
 /// 
 /// Valid values are from 0 to 262.